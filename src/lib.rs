@@ -16,14 +16,20 @@
 
 use static_assertions as sa;
 use std::borrow::Borrow;
+use std::cmp::Ordering;
 use std::ffi::OsStr;
 use std::fmt::Formatter;
+use std::hash::{Hash, Hasher};
 use std::mem::{ManuallyDrop, MaybeUninit};
 use std::ops::*;
 use std::path::Path;
+use std::sync::{Arc, OnceLock};
 
 const INLINE_CHAR_COUNT: usize = size_of::<usize>() * 4 - 1;
 const SENTINAL: u8 = 0xFF;
+const STATIC_SENTINAL: u8 = 0xFE;
+const SHARED_SENTINAL: u8 = 0xFD;
+const CONCAT_SENTINAL: u8 = 0xFC;
 
 const INLINE_AGAIN_LENGTH: usize = INLINE_CHAR_COUNT / 2;
 
@@ -68,6 +74,10 @@ impl Inline {
         self.len = 0
     }
 
+    pub fn truncate(&mut self, new_len: usize) {
+        self.len = new_len as u8;
+    }
+
     fn can_push_str(&self, string: &str) -> bool {
         self.len() + string.len() <= self.capacity()
     }
@@ -83,6 +93,39 @@ impl Inline {
         self.len += string.len() as u8;
     }
 
+    pub fn insert_str(&mut self, idx: usize, string: &str) {
+        unsafe {
+            std::ptr::copy(
+                self.chars.as_ptr().add(idx),
+                self.chars.as_mut_ptr().add(idx + string.len()),
+                self.len() - idx,
+            );
+            std::ptr::copy_nonoverlapping(
+                string.as_ptr(),
+                self.chars.as_mut_ptr().add(idx),
+                string.len(),
+            );
+        }
+        self.len += string.len() as u8;
+    }
+
+    pub fn remove(&mut self, idx: usize) -> char {
+        let c = self.as_str()[idx..]
+            .chars()
+            .next()
+            .expect("cannot remove past the end of the string");
+        let next = idx + c.len_utf8();
+        unsafe {
+            std::ptr::copy(
+                self.chars.as_ptr().add(next),
+                self.chars.as_mut_ptr().add(idx),
+                self.len() - next,
+            );
+        }
+        self.len -= c.len_utf8() as u8;
+        c
+    }
+
     pub fn try_push_str(&mut self, string: &str) -> Result<(), ()> {
         if self.can_push_str(string) {
             self.push_str(string);
@@ -118,6 +161,7 @@ impl std::fmt::Display for Inline {
 
 const PADDING: usize = size_of::<usize>() - 1;
 
+#[repr(C)]
 #[derive(Clone)]
 struct Heap {
     vec: String,
@@ -140,13 +184,33 @@ impl Heap {
         self.vec.len()
     }
 
-    pub fn clear(&mut self) {
-        self.vec.clear()
-    }
-
     pub fn push_str(&mut self, string: &str) {
         self.vec.push_str(string);
     }
+
+    pub fn truncate(&mut self, new_len: usize) {
+        self.vec.truncate(new_len)
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.vec.reserve(additional)
+    }
+
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.vec.reserve_exact(additional)
+    }
+
+    pub fn insert_str(&mut self, idx: usize, string: &str) {
+        self.vec.insert_str(idx, string)
+    }
+
+    pub fn remove(&mut self, idx: usize) -> char {
+        self.vec.remove(idx)
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.vec.shrink_to_fit()
+    }
 }
 
 impl From<String> for Heap {
@@ -171,21 +235,238 @@ impl std::fmt::Display for Heap {
     }
 }
 
-sa::assert_eq_size!(Inline, Heap);
+const STATIC_PADDING: usize = size_of::<usize>() * 2 - 1;
+
+#[repr(packed)]
+#[derive(Copy, Clone)]
+struct Static {
+    s: &'static str,
+    #[allow(unused)]
+    pad: [u8; STATIC_PADDING],
+    #[allow(unused)]
+    flag: u8,
+}
+
+impl Static {
+    pub const fn from_static(s: &'static str) -> Self {
+        Self {
+            s,
+            pad: unsafe { uninitialized() },
+            flag: STATIC_SENTINAL,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.s
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.s.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.s.len()
+    }
+}
+
+impl std::fmt::Debug for Static {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        <str as std::fmt::Debug>::fmt(self.s, f)
+    }
+}
+
+impl std::fmt::Display for Static {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        <str as std::fmt::Display>::fmt(self.s, f)
+    }
+}
 
+// `Arc<str>`-backed cheap-clone representation. Cloning this variant is a
+// refcount bump; `ShortString` never enters it on its own, only through
+// `share()` (this is opt-in, not the default `Clone` behavior for `Heap` --
+// `Clone::clone(&self) -> Self` cannot also turn `self` into a `Shared`, so
+// plain `.clone()` on a `Heap` string stays a full copy until you call
+// `share()` once).
+#[repr(C)]
+struct Shared {
+    arc: Arc<str>,
+    #[allow(unused)]
+    pad: [u8; STATIC_PADDING],
+    #[allow(unused)]
+    flag: u8,
+}
+
+impl Shared {
+    pub fn from_arc(arc: Arc<str>) -> Self {
+        Self {
+            arc,
+            pad: unsafe { uninitialized() },
+            flag: SHARED_SENTINAL,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.arc.as_ref()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.arc.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.arc.len()
+    }
+}
+
+impl Clone for Shared {
+    fn clone(&self) -> Self {
+        // Bumping the refcount is the whole point: this is an O(1) clone.
+        Self::from_arc(Arc::clone(&self.arc))
+    }
+}
+
+impl std::fmt::Debug for Shared {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        <str as std::fmt::Debug>::fmt(self.as_str(), f)
+    }
+}
+
+impl std::fmt::Display for Shared {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        <str as std::fmt::Display>::fmt(self.as_str(), f)
+    }
+}
+
+const CONCAT_PADDING: usize = size_of::<usize>() * 3 - 1;
+
+/// The deferred concatenation of a flat run of fragments. Appending a
+/// fragment never copies the existing ones; the first read materializes the
+/// full string once and caches it for subsequent reads. Parts are kept in a
+/// flat `Vec` rather than a nested tree so both appending and materializing
+/// stay iterative, with no risk of recursion depth growing with the number
+/// of appends.
+struct ConcatInner {
+    parts: Vec<Arc<ShortString>>,
+    len: usize,
+    cache: OnceLock<Box<str>>,
+}
+
+impl ConcatInner {
+    fn new(left: ShortString, right: ShortString) -> Self {
+        let len = left.len() + right.len();
+        Self {
+            parts: vec![Arc::new(left), Arc::new(right)],
+            len,
+            cache: OnceLock::new(),
+        }
+    }
+
+    fn push(&mut self, part: ShortString) {
+        self.len += part.len();
+        self.parts.push(Arc::new(part));
+        // The materialized string no longer reflects `parts`.
+        self.cache.take();
+    }
+
+    fn as_str(&self) -> &str {
+        self.cache.get_or_init(|| {
+            let mut buf = String::with_capacity(self.len);
+            for part in &self.parts {
+                buf.push_str(part.as_str());
+            }
+            buf.into_boxed_str()
+        })
+    }
+}
+
+#[repr(C)]
+struct Concat {
+    node: Box<ConcatInner>,
+    #[allow(unused)]
+    pad: [u8; CONCAT_PADDING],
+    #[allow(unused)]
+    flag: u8,
+}
+
+impl Concat {
+    fn from_node(node: Box<ConcatInner>) -> Self {
+        Self {
+            node,
+            pad: unsafe { uninitialized() },
+            flag: CONCAT_SENTINAL,
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        self.node.as_str()
+    }
+
+    fn capacity(&self) -> usize {
+        self.node.len
+    }
+
+    fn len(&self) -> usize {
+        self.node.len
+    }
+}
+
+impl Clone for Concat {
+    fn clone(&self) -> Self {
+        // Parts are shared via `Arc`; the materialized cache is not carried
+        // over and is recomputed lazily on the clone's first read.
+        Self::from_node(Box::new(ConcatInner {
+            parts: self.node.parts.iter().map(Arc::clone).collect(),
+            len: self.node.len,
+            cache: OnceLock::new(),
+        }))
+    }
+}
+
+impl std::fmt::Debug for Concat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        <str as std::fmt::Debug>::fmt(self.as_str(), f)
+    }
+}
+
+impl std::fmt::Display for Concat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        <str as std::fmt::Display>::fmt(self.as_str(), f)
+    }
+}
+
+sa::assert_eq_size!(Inline, Heap, Static, Shared, Concat);
+
+/// A small string that stores short values inline and only allocates once a
+/// value grows past that inline capacity.
+///
+/// `Clone` on a `Heap`-backed value is a full copy, the same as `String`'s:
+/// nothing promotes to the cheap-clone [`Shared`](Self::share) form on its
+/// own, since `Clone::clone(&self)` has no way to also turn `self` into a
+/// `Shared`. Call [`share`](Self::share) once to opt a value into O(1)
+/// refcounted clones before handing out copies -- e.g. before inserting into
+/// a cache, a `HashMap`, or sending down a channel.
 pub union ShortString {
     inline: Inline,
     heap: ManuallyDrop<Heap>,
+    stat: Static,
+    shared: ManuallyDrop<Shared>,
+    concat: ManuallyDrop<Concat>,
 }
 
 enum UnionVariant<'a> {
     Inline(&'a Inline),
     Heap(&'a Heap),
+    Static(&'a Static),
+    Shared(&'a Shared),
+    Concat(&'a Concat),
 }
 
 enum UnionVariantMut<'a> {
     Inline(&'a mut Inline),
     Heap(&'a mut Heap),
+    Static(&'a mut Static),
+    Shared(&'a mut Shared),
+    Concat(&'a mut Concat),
 }
 
 impl ShortString {
@@ -195,8 +476,35 @@ impl ShortString {
         }
     }
 
+    pub const fn from_static(s: &'static str) -> Self {
+        Self {
+            stat: Static::from_static(s),
+        }
+    }
+
+    #[inline(always)]
+    fn tag(&self) -> u8 {
+        unsafe { self.inline.len }
+    }
+
     pub const fn is_inline(&self) -> bool {
-        unsafe { self.inline.len != SENTINAL }
+        unsafe { self.inline.len <= INLINE_CHAR_COUNT as u8 }
+    }
+
+    const fn is_static(&self) -> bool {
+        unsafe { self.inline.len == STATIC_SENTINAL }
+    }
+
+    fn is_shared(&self) -> bool {
+        self.tag() == SHARED_SENTINAL
+    }
+
+    fn is_concat(&self) -> bool {
+        self.tag() == CONCAT_SENTINAL
+    }
+
+    fn is_heap(&self) -> bool {
+        !self.is_inline() && !self.is_static() && !self.is_shared() && !self.is_concat()
     }
 
     #[inline(always)]
@@ -204,6 +512,12 @@ impl ShortString {
         unsafe {
             if self.is_inline() {
                 UnionVariant::Inline(&self.inline)
+            } else if self.is_static() {
+                UnionVariant::Static(&self.stat)
+            } else if self.is_shared() {
+                UnionVariant::Shared(&self.shared)
+            } else if self.is_concat() {
+                UnionVariant::Concat(&self.concat)
             } else {
                 UnionVariant::Heap(&self.heap)
             }
@@ -215,16 +529,80 @@ impl ShortString {
         unsafe {
             if self.is_inline() {
                 UnionVariantMut::Inline(&mut self.inline)
+            } else if self.is_static() {
+                UnionVariantMut::Static(&mut self.stat)
+            } else if self.is_shared() {
+                UnionVariantMut::Shared(&mut self.shared)
+            } else if self.is_concat() {
+                UnionVariantMut::Concat(&mut self.concat)
             } else {
                 UnionVariantMut::Heap(&mut self.heap)
             }
         }
     }
 
+    fn promote_static(&mut self) {
+        if let UnionVariant::Static(stat) = self.variant() {
+            let owned = stat.as_str().to_owned();
+            self.heap = ManuallyDrop::new(Heap::from(owned));
+        }
+    }
+
+    // Always copies, even when `Arc::strong_count` is 1: `Arc<str>` is an
+    // unsized, exact-fit allocation with no spare capacity to grow into, so
+    // there's no in-place path for a growing mutation like `push_str` to
+    // take regardless of uniqueness. The refcount only matters for
+    // correctness (whether the bytes may be aliased), not for avoiding this
+    // copy.
+    fn promote_shared(&mut self) {
+        if self.is_shared() {
+            let owned = unsafe { self.shared.as_str().to_owned() };
+            unsafe { ManuallyDrop::drop(&mut self.shared) };
+            self.heap = ManuallyDrop::new(Heap::from(owned));
+        }
+    }
+
+    /// Appends `string` without copying `self`'s existing bytes: extends the
+    /// current `Concat` in place, or starts one out of `self` and `string`.
+    fn concat_with(&mut self, string: &str) {
+        let right = ShortString::from(string);
+        if self.is_concat() {
+            unsafe { self.concat.node.push(right) };
+        } else {
+            let left = std::mem::take(self);
+            let node = Box::new(ConcatInner::new(left, right));
+            self.concat = ManuallyDrop::new(Concat::from_node(node));
+        }
+    }
+
+    fn promote_concat(&mut self) {
+        if self.is_concat() {
+            let owned = unsafe { self.concat.as_str() }.to_owned();
+            unsafe { ManuallyDrop::drop(&mut self.concat) };
+            self.heap = ManuallyDrop::new(Heap::from(owned));
+        }
+    }
+
+    // Threshold is half of `INLINE_CHAR_COUNT`, not the full inline
+    // capacity, so strings hovering at the boundary don't thrash between
+    // representations.
+    fn demote_if_small(&mut self) {
+        if let UnionVariant::Heap(heap) = self.variant() {
+            if heap.len() <= INLINE_AGAIN_LENGTH {
+                let inline = Inline::from(heap.as_str());
+                unsafe { ManuallyDrop::drop(&mut self.heap) };
+                self.inline = inline;
+            }
+        }
+    }
+
     pub fn as_str(&self) -> &str {
         match self.variant() {
             UnionVariant::Inline(inline) => inline.as_str(),
             UnionVariant::Heap(heap) => heap.as_str(),
+            UnionVariant::Static(stat) => stat.as_str(),
+            UnionVariant::Shared(shared) => shared.as_str(),
+            UnionVariant::Concat(concat) => concat.as_str(),
         }
     }
 
@@ -236,6 +614,9 @@ impl ShortString {
         match self.variant() {
             UnionVariant::Inline(inline) => inline.capacity(),
             UnionVariant::Heap(heap) => heap.capacity(),
+            UnionVariant::Static(stat) => stat.capacity(),
+            UnionVariant::Shared(shared) => shared.capacity(),
+            UnionVariant::Concat(concat) => concat.capacity(),
         }
     }
 
@@ -243,37 +624,253 @@ impl ShortString {
         match self.variant() {
             UnionVariant::Inline(inline) => inline.len(),
             UnionVariant::Heap(heap) => heap.len(),
+            UnionVariant::Static(stat) => stat.len(),
+            UnionVariant::Shared(shared) => shared.len(),
+            UnionVariant::Concat(concat) => concat.len(),
         }
     }
 
     pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Appends `string` in place, promoting straight to `Heap` once it no
+    /// longer fits inline -- the same amortized growth `String::push_str`
+    /// uses, with no `Concat` node involved. Building a rope out of
+    /// single-fragment appends would otherwise allocate one `Arc` per call;
+    /// use [`Add`](Self::add)/[`AddAssign`] or [`Extend`] instead when
+    /// deferring a run of fragments is the goal.
+    pub fn push_str(&mut self, string: &str) {
+        if string.is_empty() {
+            return;
+        }
+        self.promote_static();
+        self.promote_shared();
+        self.promote_concat();
+        let fits_inline =
+            matches!(self.variant(), UnionVariant::Inline(inline) if inline.can_push_str(string));
+        if fits_inline {
+            if let UnionVariantMut::Inline(inline) = self.variant_mut() {
+                inline.push_str(string);
+            }
+        } else if self.is_heap() {
+            // Heap already owns a growable buffer: appending in place keeps
+            // this amortized O(1), same as `String::push_str`.
+            if let UnionVariantMut::Heap(heap) = self.variant_mut() {
+                heap.push_str(string);
+            }
+        } else {
+            // Only `Inline` can reach here: `Static`/`Shared`/`Concat` were
+            // already promoted away above, and `Heap` was just handled.
+            let mut owned = String::with_capacity(self.len() + string.len());
+            owned.push_str(self.as_str());
+            owned.push_str(string);
+            self.heap = ManuallyDrop::new(Heap::from(owned));
+        }
+    }
+
+    /// Appends `string`, copying it into the inline buffer while the result
+    /// still fits there; otherwise appends without copying `self`'s existing
+    /// bytes: extends the current `Concat` in place, or starts one out of
+    /// `self` and `string`.
+    /// Used by operations that build up a run of fragments
+    /// (`+=`/[`Add`](Self::add)/[`Extend`]) rather than a single amortized
+    /// append; see [`push_str`](Self::push_str) for that case.
+    fn concat_append(&mut self, string: &str) {
+        if string.is_empty() {
+            return;
+        }
+        self.promote_static();
+        self.promote_shared();
+        let fits_inline =
+            matches!(self.variant(), UnionVariant::Inline(inline) if inline.can_push_str(string));
+        if fits_inline {
+            if let UnionVariantMut::Inline(inline) = self.variant_mut() {
+                inline.push_str(string);
+            }
+        } else {
+            self.concat_with(string);
+        }
+    }
+
+    /// Converts this string into a reference-counted `Shared` representation
+    /// and returns a second handle to the same buffer. Cloning either handle
+    /// afterwards is an O(1) refcount bump; mutating either one copies the
+    /// bytes out into a private `Heap` buffer first.
+    pub fn share(&mut self) -> Self {
+        if !self.is_shared() {
+            let arc: Arc<str> = Arc::from(self.as_str());
+            unsafe {
+                match self.variant() {
+                    UnionVariant::Heap(_) => ManuallyDrop::drop(&mut self.heap),
+                    UnionVariant::Concat(_) => ManuallyDrop::drop(&mut self.concat),
+                    _ => {}
+                }
+            }
+            self.shared = ManuallyDrop::new(Shared::from_arc(arc));
+        }
+        let shared = unsafe { (*self.shared).clone() };
+        Self {
+            shared: ManuallyDrop::new(shared),
+        }
+    }
+
+    pub fn push(&mut self, c: char) {
+        let mut buffer: [u8; 4] = unsafe { uninitialized() };
+        let string = c.encode_utf8(&mut buffer);
+        self.push_str(string)
+    }
+
+    /// Shortens this string to `new_len` bytes. `new_len` must lie on a
+    /// `char` boundary; a `Heap` representation that shrinks to
+    /// `INLINE_AGAIN_LENGTH` bytes or fewer is demoted back to `Inline`.
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len >= self.len() {
+            return;
+        }
+        assert!(self.as_str().is_char_boundary(new_len));
+        self.promote_static();
+        self.promote_shared();
+        self.promote_concat();
         match self.variant_mut() {
-            UnionVariantMut::Inline(inline) => inline.clear(),
-            UnionVariantMut::Heap(heap) => heap.clear(),
+            UnionVariantMut::Inline(inline) => inline.truncate(new_len),
+            UnionVariantMut::Heap(heap) => heap.truncate(new_len),
+            UnionVariantMut::Static(_) | UnionVariantMut::Shared(_) | UnionVariantMut::Concat(_) => {
+                unreachable!()
+            }
         }
+        self.demote_if_small();
     }
 
-    pub fn push_str(&mut self, string: &str) {
+    /// Removes and returns the last `char`, or `None` if the string is
+    /// empty.
+    pub fn pop(&mut self) -> Option<char> {
+        let c = self.as_str().chars().next_back()?;
+        self.truncate(self.len() - c.len_utf8());
+        Some(c)
+    }
+
+    /// Validates `bytes` as UTF-8 and returns the equivalent `ShortString`,
+    /// or the `Utf8Error` if it isn't valid.
+    pub fn from_utf8(bytes: &[u8]) -> Result<Self, std::str::Utf8Error> {
+        Self::try_from(bytes)
+    }
+
+    /// Creates an empty string preallocated to hold at least `capacity`
+    /// bytes without reallocating, staying `Inline` when it fits.
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity <= INLINE_CHAR_COUNT {
+            Self::new()
+        } else {
+            Self {
+                heap: ManuallyDrop::new(Heap::from(String::with_capacity(capacity))),
+            }
+        }
+    }
+
+    /// Reserves capacity for at least `additional` more bytes, promoting an
+    /// `Inline` representation to `Heap` if it would overflow.
+    pub fn reserve(&mut self, additional: usize) {
+        self.promote_static();
+        self.promote_shared();
+        self.promote_concat();
         match self.variant_mut() {
             UnionVariantMut::Inline(inline) => {
-                if let Err(_) = inline.try_push_str(string) {
-                    let mut s = String::new();
-                    s.reserve(inline.len() + string.len());
+                if inline.len() + additional > INLINE_CHAR_COUNT {
+                    let mut s = String::with_capacity(inline.len() + additional);
                     s.push_str(inline.as_str());
-                    s.push_str(string);
-                    self.heap = ManuallyDrop::new(s.into())
+                    self.heap = ManuallyDrop::new(Heap::from(s));
                 }
             }
-            UnionVariantMut::Heap(heap) => {
-                heap.push_str(string);
+            UnionVariantMut::Heap(heap) => heap.reserve(additional),
+            UnionVariantMut::Static(_) | UnionVariantMut::Shared(_) | UnionVariantMut::Concat(_) => {
+                unreachable!()
             }
         }
     }
 
-    pub fn push(&mut self, c: char) {
+    /// Like `reserve`, but does not over-allocate once promoted to `Heap`.
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.promote_static();
+        self.promote_shared();
+        self.promote_concat();
+        match self.variant_mut() {
+            UnionVariantMut::Inline(inline) => {
+                if inline.len() + additional > INLINE_CHAR_COUNT {
+                    let mut s = String::with_capacity(inline.len() + additional);
+                    s.push_str(inline.as_str());
+                    self.heap = ManuallyDrop::new(Heap::from(s));
+                }
+            }
+            UnionVariantMut::Heap(heap) => heap.reserve_exact(additional),
+            UnionVariantMut::Static(_) | UnionVariantMut::Shared(_) | UnionVariantMut::Concat(_) => {
+                unreachable!()
+            }
+        }
+    }
+
+    /// Inserts `c` at byte index `idx`, which must lie on a `char` boundary.
+    pub fn insert(&mut self, idx: usize, c: char) {
         let mut buffer: [u8; 4] = unsafe { uninitialized() };
         let string = c.encode_utf8(&mut buffer);
-        self.push_str(string)
+        self.insert_str(idx, string);
+    }
+
+    /// Inserts `string` at byte index `idx`, which must lie on a `char`
+    /// boundary.
+    pub fn insert_str(&mut self, idx: usize, string: &str) {
+        assert!(self.as_str().is_char_boundary(idx));
+        if string.is_empty() {
+            return;
+        }
+        self.promote_static();
+        self.promote_shared();
+        self.promote_concat();
+        let fits_inline = matches!(
+            self.variant(),
+            UnionVariant::Inline(inline) if inline.len() + string.len() <= INLINE_CHAR_COUNT
+        );
+        match self.variant_mut() {
+            UnionVariantMut::Inline(inline) if fits_inline => inline.insert_str(idx, string),
+            UnionVariantMut::Inline(inline) => {
+                let mut s = String::with_capacity(inline.len() + string.len());
+                s.push_str(inline.as_str());
+                s.insert_str(idx, string);
+                self.heap = ManuallyDrop::new(Heap::from(s));
+            }
+            UnionVariantMut::Heap(heap) => heap.insert_str(idx, string),
+            UnionVariantMut::Static(_) | UnionVariantMut::Shared(_) | UnionVariantMut::Concat(_) => {
+                unreachable!()
+            }
+        }
+    }
+
+    /// Removes and returns the `char` at byte index `idx`.
+    pub fn remove(&mut self, idx: usize) -> char {
+        self.promote_static();
+        self.promote_shared();
+        self.promote_concat();
+        let c = match self.variant_mut() {
+            UnionVariantMut::Inline(inline) => inline.remove(idx),
+            UnionVariantMut::Heap(heap) => heap.remove(idx),
+            UnionVariantMut::Static(_) | UnionVariantMut::Shared(_) | UnionVariantMut::Concat(_) => {
+                unreachable!()
+            }
+        };
+        self.demote_if_small();
+        c
+    }
+
+    /// Releases any excess heap capacity, demoting back to `Inline` when
+    /// `INLINE_AGAIN_LENGTH` bytes or fewer remain.
+    pub fn shrink_to_fit(&mut self) {
+        self.promote_static();
+        self.promote_shared();
+        self.promote_concat();
+        self.demote_if_small();
+        if let UnionVariantMut::Heap(heap) = self.variant_mut() {
+            heap.shrink_to_fit();
+        }
     }
 }
 
@@ -288,8 +885,11 @@ impl Default for ShortString {
 impl Drop for ShortString {
     fn drop(&mut self) {
         unsafe {
-            if !self.is_inline() {
-                ManuallyDrop::drop(&mut self.heap)
+            match self.variant() {
+                UnionVariant::Heap(_) => ManuallyDrop::drop(&mut self.heap),
+                UnionVariant::Shared(_) => ManuallyDrop::drop(&mut self.shared),
+                UnionVariant::Concat(_) => ManuallyDrop::drop(&mut self.concat),
+                UnionVariant::Inline(_) | UnionVariant::Static(_) => {}
             }
         }
     }
@@ -304,6 +904,13 @@ impl Clone for ShortString {
             UnionVariant::Heap(heap) => Self {
                 heap: ManuallyDrop::new(heap.clone()),
             },
+            UnionVariant::Static(stat) => Self { stat: *stat },
+            UnionVariant::Shared(shared) => Self {
+                shared: ManuallyDrop::new(shared.clone()),
+            },
+            UnionVariant::Concat(concat) => Self {
+                concat: ManuallyDrop::new(concat.clone()),
+            },
         }
     }
 }
@@ -332,6 +939,13 @@ impl From<String> for ShortString {
     }
 }
 
+impl TryFrom<&[u8]> for ShortString {
+    type Error = std::str::Utf8Error;
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        std::str::from_utf8(value).map(ShortString::from)
+    }
+}
+
 impl Deref for ShortString {
     type Target = str;
     fn deref(&self) -> &Self::Target {
@@ -363,6 +977,71 @@ impl Borrow<str> for ShortString {
     }
 }
 
+impl PartialEq for ShortString {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for ShortString {}
+
+impl PartialEq<str> for ShortString {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<ShortString> for str {
+    fn eq(&self, other: &ShortString) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl PartialEq<&str> for ShortString {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<ShortString> for &str {
+    fn eq(&self, other: &ShortString) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl PartialEq<String> for ShortString {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl PartialEq<ShortString> for String {
+    fn eq(&self, other: &ShortString) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Hash for ShortString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Hashing the `str` bytes (rather than the raw representation) is
+        // what keeps this consistent with the `Borrow<str>` impl above, and
+        // with `str`/`String` when both are used as `HashMap` keys.
+        self.as_str().hash(state)
+    }
+}
+
+impl PartialOrd for ShortString {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ShortString {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
 impl Add<&str> for ShortString {
     type Output = Self;
     fn add(mut self, rhs: &str) -> Self::Output {
@@ -373,7 +1052,7 @@ impl Add<&str> for ShortString {
 
 impl AddAssign<&str> for ShortString {
     fn add_assign(&mut self, rhs: &str) {
-        self.push_str(rhs);
+        self.concat_append(rhs);
     }
 }
 
@@ -385,7 +1064,44 @@ impl Extend<char> for ShortString {
 
 impl<'a> Extend<&'a str> for ShortString {
     fn extend<T: IntoIterator<Item = &'a str>>(&mut self, iter: T) {
-        iter.into_iter().for_each(|s| self.push_str(s));
+        iter.into_iter().for_each(|s| self.concat_append(s));
+    }
+}
+
+impl Extend<String> for ShortString {
+    fn extend<T: IntoIterator<Item = String>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|s| self.concat_append(&s));
+    }
+}
+
+impl FromIterator<char> for ShortString {
+    fn from_iter<T: IntoIterator<Item = char>>(iter: T) -> Self {
+        let mut s = Self::new();
+        s.extend(iter);
+        s
+    }
+}
+
+impl<'a> FromIterator<&'a str> for ShortString {
+    fn from_iter<T: IntoIterator<Item = &'a str>>(iter: T) -> Self {
+        let mut s = Self::new();
+        s.extend(iter);
+        s
+    }
+}
+
+impl FromIterator<String> for ShortString {
+    fn from_iter<T: IntoIterator<Item = String>>(iter: T) -> Self {
+        let mut s = Self::new();
+        s.extend(iter);
+        s
+    }
+}
+
+impl std::fmt::Write for ShortString {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.push_str(s);
+        Ok(())
     }
 }
 
@@ -394,6 +1110,9 @@ impl std::fmt::Debug for ShortString {
         match self.variant() {
             UnionVariant::Inline(inline) => <Inline as std::fmt::Debug>::fmt(inline, f),
             UnionVariant::Heap(heap) => <Heap as std::fmt::Debug>::fmt(heap, f),
+            UnionVariant::Static(stat) => <Static as std::fmt::Debug>::fmt(stat, f),
+            UnionVariant::Shared(shared) => <Shared as std::fmt::Debug>::fmt(shared, f),
+            UnionVariant::Concat(concat) => <Concat as std::fmt::Debug>::fmt(concat, f),
         }
     }
 }
@@ -403,6 +1122,294 @@ impl std::fmt::Display for ShortString {
         match self.variant() {
             UnionVariant::Inline(inline) => <Inline as std::fmt::Display>::fmt(inline, f),
             UnionVariant::Heap(heap) => <Heap as std::fmt::Display>::fmt(heap, f),
+            UnionVariant::Static(stat) => <Static as std::fmt::Display>::fmt(stat, f),
+            UnionVariant::Shared(shared) => <Shared as std::fmt::Display>::fmt(shared, f),
+            UnionVariant::Concat(concat) => <Concat as std::fmt::Display>::fmt(concat, f),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn long(n: usize) -> String {
+        "a".repeat(n)
+    }
+
+    /// One instance of each representation, for tests that need to check a
+    /// mutation round-trips no matter which variant it starts from.
+    fn sample_variants() -> Vec<ShortString> {
+        vec![
+            ShortString::from("short"),
+            ShortString::from(long(INLINE_CHAR_COUNT + 1)),
+            ShortString::from_static("a static string literal"),
+            {
+                let mut shared = ShortString::from(long(INLINE_CHAR_COUNT + 1));
+                shared.share()
+            },
+            {
+                let mut concat = ShortString::new();
+                for _ in 0..64 {
+                    concat += "abcd";
+                }
+                concat
+            },
+        ]
+    }
+
+    #[test]
+    fn demotes_to_inline_once_shrunk_past_the_hysteresis_threshold() {
+        let mut s = ShortString::from(long(INLINE_CHAR_COUNT + 1));
+        assert!(s.is_heap());
+
+        // Still above `INLINE_AGAIN_LENGTH`: stays `Heap`.
+        s.truncate(INLINE_AGAIN_LENGTH + 1);
+        assert!(s.is_heap());
+
+        // At `INLINE_AGAIN_LENGTH`: demotes back to `Inline`.
+        s.truncate(INLINE_AGAIN_LENGTH);
+        assert!(s.is_inline());
+        assert_eq!(s.len(), INLINE_AGAIN_LENGTH);
+    }
+
+    #[test]
+    fn demotion_threshold_does_not_thrash_right_at_the_boundary() {
+        let mut s = ShortString::from(long(INLINE_AGAIN_LENGTH + 1 + INLINE_CHAR_COUNT));
+        s.truncate(INLINE_AGAIN_LENGTH + 1);
+        assert!(s.is_heap());
+        s.pop();
+        assert!(s.is_inline());
+        assert_eq!(s.len(), INLINE_AGAIN_LENGTH);
+    }
+
+    #[test]
+    fn share_bumps_refcount_and_mutation_copies_back_out() {
+        let mut a = ShortString::from(long(INLINE_CHAR_COUNT + 1));
+        let b = a.share();
+        assert!(a.is_shared());
+        assert!(b.is_shared());
+        assert_eq!(unsafe { Arc::strong_count(&a.shared.arc) }, 2);
+        assert_eq!(a.as_str(), b.as_str());
+
+        // Mutating one handle must not affect the other, and must leave
+        // shared storage behind for the handle that didn't mutate.
+        a.push_str("more");
+        assert!(!a.is_shared());
+        assert!(b.is_shared());
+        assert_eq!(unsafe { Arc::strong_count(&b.shared.arc) }, 1);
+        assert_ne!(a.as_str(), b.as_str());
+        assert!(a.as_str().ends_with("more"));
+    }
+
+    #[test]
+    fn sharing_an_already_shared_string_reuses_the_same_allocation() {
+        let mut a = ShortString::from(long(INLINE_CHAR_COUNT + 1));
+        let _b = a.share();
+        let c = a.share();
+        assert_eq!(unsafe { Arc::strong_count(&a.shared.arc) }, 3);
+        assert_eq!(a.as_str(), c.as_str());
+    }
+
+    #[test]
+    fn push_str_past_inline_capacity_promotes_to_heap() {
+        let mut s = ShortString::new();
+        assert!(s.is_inline());
+        for _ in 0..64 {
+            s.push_str("abcd");
+        }
+        assert!(s.is_heap());
+        assert_eq!(s.len(), 64 * 4);
+        assert_eq!(s.as_str(), "abcd".repeat(64));
+    }
+
+    #[test]
+    fn push_does_not_grow_a_concat_node_per_call() {
+        // Regression test: `push` (and `push_str`) must promote straight to
+        // `Heap` once inline capacity is exceeded, not allocate a fresh
+        // `Concat` leaf on every call.
+        let mut s = ShortString::new();
+        for _ in 0..2000 {
+            s.push('a');
+        }
+        assert!(s.is_heap());
+        assert_eq!(s.len(), 2000);
+        assert_eq!(s.as_str(), "a".repeat(2000));
+    }
+
+    #[test]
+    fn add_assign_past_inline_capacity_builds_a_concat_then_materializes() {
+        let mut s = ShortString::new();
+        assert!(s.is_inline());
+        for _ in 0..64 {
+            s += "abcd";
+        }
+        assert!(s.is_concat());
+        assert_eq!(s.len(), 64 * 4);
+        assert_eq!(s.as_str(), "abcd".repeat(64));
+    }
+
+    #[test]
+    fn truncate_round_trips_through_every_variant() {
+        for mut s in sample_variants() {
+            let expected_prefix = s.as_str()[..1].to_owned();
+            s.truncate(1);
+            assert_eq!(s.as_str(), expected_prefix);
+        }
+    }
+
+    #[test]
+    fn remove_round_trips_through_every_variant() {
+        for mut s in sample_variants() {
+            let first = s.as_str().chars().next().unwrap();
+            let rest: String = s.as_str().chars().skip(1).collect();
+            let removed = s.remove(0);
+            assert_eq!(removed, first);
+            assert_eq!(s.as_str(), rest);
+        }
+    }
+
+    #[test]
+    fn push_str_round_trips_through_every_variant() {
+        for mut s in sample_variants() {
+            let mut expected = s.as_str().to_owned();
+            expected.push_str("-suffix");
+            s.push_str("-suffix");
+            assert_eq!(s.as_str(), expected);
+        }
+    }
+
+    #[test]
+    fn from_static_reports_its_contents_without_allocating() {
+        let s = ShortString::from_static("a static string literal");
+        assert!(s.is_static());
+        assert_eq!(s.as_str(), "a static string literal");
+        assert_eq!(s.len(), "a static string literal".len());
+    }
+
+    #[test]
+    fn from_static_clone_is_cheap_and_stays_static() {
+        let s = ShortString::from_static("a static string literal");
+        let cloned = s.clone();
+        assert!(cloned.is_static());
+        assert_eq!(cloned.as_str(), s.as_str());
+    }
+
+    #[test]
+    fn equality_and_ordering_match_str_semantics() {
+        let a = ShortString::from("apple");
+        let b = ShortString::from("banana");
+        assert_eq!(a, ShortString::from("apple"));
+        assert_eq!(a, "apple");
+        assert_eq!(a, *"apple");
+        assert_eq!(a, String::from("apple"));
+        assert_ne!(a, b);
+        assert!(a < b);
+        assert_eq!(a.cmp(&b), Ordering::Less);
+    }
+
+    #[test]
+    fn hash_matches_equal_values_and_works_as_a_map_key() {
+        use std::collections::HashMap;
+
+        // `Concat`'s cache is interior mutability (`OnceLock`), which trips
+        // `clippy::mutable_key_type`; it's sound here because `Hash`/`Eq`
+        // only ever read `as_str()`, which the cache never changes.
+        #[allow(clippy::mutable_key_type)]
+        let mut map: HashMap<ShortString, i32> = HashMap::new();
+        map.insert(ShortString::from("key"), 1);
+        assert_eq!(map.get(&ShortString::from("key")), Some(&1));
+
+        let mut hasher_a = std::collections::hash_map::DefaultHasher::new();
+        ShortString::from("same").hash(&mut hasher_a);
+        let mut hasher_b = std::collections::hash_map::DefaultHasher::new();
+        ShortString::from("same").hash(&mut hasher_b);
+        assert_eq!(hasher_a.finish(), hasher_b.finish());
+    }
+
+    #[test]
+    fn write_fmt_appends_formatted_output() {
+        use std::fmt::Write;
+
+        let mut s = ShortString::from("count: ");
+        let n = 1;
+        let word = "two";
+        write!(s, "{n}-{word}").unwrap();
+        assert_eq!(s.as_str(), "count: 1-two");
+    }
+
+    #[test]
+    fn from_iter_char_builds_the_same_string_as_string() {
+        let s: ShortString = "hello".chars().collect();
+        assert_eq!(s.as_str(), "hello");
+    }
+
+    #[test]
+    fn from_iter_str_fragments_builds_a_concat() {
+        let s: ShortString = ["abcd"; 64].into_iter().collect();
+        assert!(s.is_concat());
+        assert_eq!(s.as_str(), "abcd".repeat(64));
+    }
+
+    #[test]
+    fn from_iter_string_fragments_builds_the_same_string() {
+        let s: ShortString = vec![String::from("ab"), String::from("cd")]
+            .into_iter()
+            .collect();
+        assert_eq!(s.as_str(), "abcd");
+    }
+
+    #[test]
+    fn from_utf8_accepts_valid_and_rejects_invalid_bytes() {
+        let s = ShortString::from_utf8("hello".as_bytes()).unwrap();
+        assert_eq!(s.as_str(), "hello");
+
+        let invalid = [0xff, 0xfe];
+        assert!(ShortString::from_utf8(&invalid).is_err());
+    }
+
+    #[test]
+    fn with_capacity_stays_inline_or_preallocates_heap() {
+        let inline = ShortString::with_capacity(INLINE_CHAR_COUNT);
+        assert!(inline.is_inline());
+
+        let heap = ShortString::with_capacity(INLINE_CHAR_COUNT + 1);
+        assert!(heap.is_heap());
+        assert!(heap.capacity() > INLINE_CHAR_COUNT);
+    }
+
+    #[test]
+    fn reserve_and_reserve_exact_promote_past_inline_capacity() {
+        let mut s = ShortString::from("short");
+        assert!(s.is_inline());
+        s.reserve(INLINE_CHAR_COUNT);
+        assert!(s.is_heap());
+        assert!(s.capacity() > INLINE_CHAR_COUNT);
+
+        let mut s = ShortString::from("short");
+        s.reserve_exact(INLINE_CHAR_COUNT);
+        assert!(s.is_heap());
+        assert!(s.capacity() > INLINE_CHAR_COUNT);
+    }
+
+    #[test]
+    fn insert_and_insert_str_shift_existing_bytes() {
+        let mut s = ShortString::from("helo");
+        s.insert(2, 'l');
+        assert_eq!(s.as_str(), "hello");
+
+        let mut s = ShortString::from("ho");
+        s.insert_str(1, "ell");
+        assert_eq!(s.as_str(), "hello");
+    }
+
+    #[test]
+    fn shrink_to_fit_cooperates_with_heap_to_inline_demotion() {
+        let mut s = ShortString::with_capacity(INLINE_CHAR_COUNT * 4);
+        s.push_str("short");
+        assert!(s.is_heap());
+        s.shrink_to_fit();
+        assert!(s.is_inline());
+        assert_eq!(s.as_str(), "short");
+    }
+}